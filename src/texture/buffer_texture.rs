@@ -6,6 +6,7 @@ alternative to uniform buffers and SSBOs.
 
 
 */
+use std::cell::Cell;
 use std::mem;
 use std::ops::{Deref, DerefMut};
 
@@ -33,6 +34,12 @@ pub enum TextureCreationError {
 
     /// The size of the buffer that you are trying to bind exceeds `GL_MAX_TEXTURE_BUFFER_SIZE`.
     TooLarge,
+
+    /// The requested offset is not a multiple of `GL_TEXTURE_BUFFER_OFFSET_ALIGNMENT`.
+    BadAlignment,
+
+    /// The requested `offset`/`size` range falls outside of the buffer being sliced.
+    OutOfRange,
 }
 
 /// Error that can happen while building a buffer texture.
@@ -77,11 +84,133 @@ pub enum BufferTextureType {
     Unsigned,
 }
 
+/// How the components of an `InternalFormat` are interpreted when sampled in a shader.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InternalFormatKind {
+    /// Integer components, normalized to `[0, 1]` when sampled (`samplerBuffer`).
+    UnsignedNormalized,
+    /// Unsigned integer components, read back as-is (`usamplerBuffer`).
+    UnsignedInteger,
+    /// Signed integer components, read back as-is (`isamplerBuffer`).
+    SignedInteger,
+    /// Floating-point components (`samplerBuffer`).
+    Float,
+}
+
+/// Describes an OpenGL internal format usable by a buffer texture: its raw `gl::` enum, its
+/// component layout, and how those components are interpreted.
+///
+/// This is what `internal_format`/`client_format` produce and consume, so that code that needs
+/// the number of bytes per texel has a single place to get it from, instead of re-deriving it
+/// from the `gl::` enum every time. The `TooLarge` check counts texels and does not use it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InternalFormat {
+    /// The raw OpenGL internal format, for example `gl::RGBA8`.
+    pub gl_enum: gl::types::GLenum,
+    /// Number of components, from 1 to 4.
+    pub components: u8,
+    /// Number of bytes used to store a single component.
+    pub bytes_per_component: u8,
+    /// How the components are interpreted when sampled.
+    pub kind: InternalFormatKind,
+    /// Whether this format additionally requires GL 4.0 or
+    /// `GL_ARB_texture_buffer_object_rgb32` on top of core buffer texture support.
+    pub requires_rgb32_ext: bool,
+}
+
+impl InternalFormat {
+    /// Number of bytes taken by a single texel stored in this format.
+    pub fn bytes_per_texel(&self) -> usize {
+        self.components as usize * self.bytes_per_component as usize
+    }
+}
+
+/// Returns the `InternalFormat` to use for a given content/texture-type pair, or `None` if
+/// OpenGL has no internal format matching that combination.
+fn internal_format(sized_type: TextureBufferContentType, kind: BufferTextureType)
+                   -> Option<InternalFormat>
+{
+    use self::TextureBufferContentType::*;
+    use self::InternalFormatKind::*;
+
+    let (gl_enum, components, bytes_per_component, format_kind, requires_rgb32_ext) =
+        match (sized_type, kind) {
+            (U8, BufferTextureType::Float) => (gl::R8, 1, 1, UnsignedNormalized, false),
+            (U8, BufferTextureType::Unsigned) => (gl::R8UI, 1, 1, UnsignedInteger, false),
+            (I8, BufferTextureType::Integral) => (gl::R8I, 1, 1, SignedInteger, false),
+            (U16, BufferTextureType::Float) => (gl::R16, 1, 2, UnsignedNormalized, false),
+            (U16, BufferTextureType::Unsigned) => (gl::R16UI, 1, 2, UnsignedInteger, false),
+            (I16, BufferTextureType::Integral) => (gl::R16I, 1, 2, SignedInteger, false),
+            (U32, BufferTextureType::Unsigned) => (gl::R32UI, 1, 4, UnsignedInteger, false),
+            (I32, BufferTextureType::Integral) => (gl::R32I, 1, 4, SignedInteger, false),
+            (U8U8, BufferTextureType::Float) => (gl::RG8, 2, 1, UnsignedNormalized, false),
+            (U8U8, BufferTextureType::Unsigned) => (gl::RG8UI, 2, 1, UnsignedInteger, false),
+            (I8I8, BufferTextureType::Integral) => (gl::RG8I, 2, 1, SignedInteger, false),
+            (U16U16, BufferTextureType::Float) => (gl::RG16, 2, 2, UnsignedNormalized, false),
+            (U16U16, BufferTextureType::Unsigned) => (gl::RG16UI, 2, 2, UnsignedInteger, false),
+            (I16I16, BufferTextureType::Integral) => (gl::RG16I, 2, 2, SignedInteger, false),
+            (U32U32, BufferTextureType::Unsigned) => (gl::RG32UI, 2, 4, UnsignedInteger, false),
+            (I32I32, BufferTextureType::Integral) => (gl::RG32I, 2, 4, SignedInteger, false),
+            (U8U8U8U8, BufferTextureType::Float) => (gl::RGBA8, 4, 1, UnsignedNormalized, false),
+            (U8U8U8U8, BufferTextureType::Unsigned) => (gl::RGBA8UI, 4, 1, UnsignedInteger, false),
+            (I8I8I8I8, BufferTextureType::Integral) => (gl::RGBA8I, 4, 1, SignedInteger, false),
+            (U16U16U16U16, BufferTextureType::Float) => (gl::RGBA16, 4, 2, UnsignedNormalized, false),
+            (U16U16U16U16, BufferTextureType::Unsigned) =>
+                                                      (gl::RGBA16UI, 4, 2, UnsignedInteger, false),
+            (I16I16I16I16, BufferTextureType::Integral) =>
+                                                      (gl::RGBA16I, 4, 2, SignedInteger, false),
+            (U32U32U32U32, BufferTextureType::Unsigned) =>
+                                                      (gl::RGBA32UI, 4, 4, UnsignedInteger, false),
+            (I32I32I32I32, BufferTextureType::Integral) =>
+                                                      (gl::RGBA32I, 4, 4, SignedInteger, false),
+            (F16, BufferTextureType::Float) => (gl::R16F, 1, 2, Float, false),
+            (F32, BufferTextureType::Float) => (gl::R32F, 1, 4, Float, false),
+            (F16F16, BufferTextureType::Float) => (gl::RG16F, 2, 2, Float, false),
+            (F32F32, BufferTextureType::Float) => (gl::RG32F, 2, 4, Float, false),
+            (F16F16F16F16, BufferTextureType::Float) => (gl::RGBA16F, 4, 2, Float, false),
+            (F32F32F32F32, BufferTextureType::Float) => (gl::RGBA32F, 4, 4, Float, false),
+
+            // three-component formats additionally require GL 4.0 or
+            // GL_ARB_texture_buffer_object_rgb32, checked by the caller via `requires_rgb32_ext`
+            (U32U32U32, BufferTextureType::Unsigned) => (gl::RGB32UI, 3, 4, UnsignedInteger, true),
+            (I32I32I32, BufferTextureType::Integral) => (gl::RGB32I, 3, 4, SignedInteger, true),
+            (F32F32F32, BufferTextureType::Float) => (gl::RGB32F, 3, 4, Float, true),
+
+            _ => return None,
+        };
+
+    Some(InternalFormat {
+        gl_enum: gl_enum,
+        components: components,
+        bytes_per_component: bytes_per_component,
+        kind: format_kind,
+        requires_rgb32_ext: requires_rgb32_ext,
+    })
+}
+
+/// Returns the `BufferTextureType` that a shader would use to sample back an `InternalFormat`,
+/// the inverse of `internal_format`.
+pub fn client_format(format: &InternalFormat) -> BufferTextureType {
+    match format.kind {
+        InternalFormatKind::UnsignedNormalized | InternalFormatKind::Float => BufferTextureType::Float,
+        InternalFormatKind::UnsignedInteger => BufferTextureType::Unsigned,
+        InternalFormatKind::SignedInteger => BufferTextureType::Integral,
+    }
+}
+
+thread_local!(
+    // `GL_MAX_TEXTURE_BUFFER_SIZE` never changes for the lifetime of a context. The value is
+    // cached once per thread, not per context: the first context queried on a thread supplies
+    // the value that every context later made current on that same thread reuses.
+    static MAX_TEXTURE_BUFFER_SIZE: Cell<Option<gl::types::GLint>> = Cell::new(None)
+);
+
 /// A one-dimensional texture that gets its data from a buffer.
 pub struct BufferTexture<T> where [T]: BufferContent {
     buffer: BufferView<[T]>,
     texture: gl::types::GLuint,
     ty: BufferTextureType,
+    format: InternalFormat,
 }
 
 impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Copy {
@@ -169,145 +298,277 @@ impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Cop
     pub fn from_buffer<F>(context: &F, buffer: BufferView<[T]>, ty: BufferTextureType)
                           -> Result<BufferTexture<T>, (TextureCreationError, BufferView<[T]>)>
                           where F: Facade
+    {
+        BufferTexture::from_buffer_impl(context, buffer, ty, None)
+    }
+
+    /// Builds a new buffer texture that only exposes a sub-range of the given buffer.
+    ///
+    /// `offset` and `size` are expressed in number of elements of `T`, not in bytes. This makes
+    /// it possible to carve out several disjoint `BufferTexture`s from a single, larger
+    /// `BufferView`, instead of having to allocate one buffer per texture.
+    ///
+    /// `offset` must be aligned on `GL_TEXTURE_BUFFER_OFFSET_ALIGNMENT` bytes, or
+    /// `TextureCreationError::BadAlignment` is returned.
+    pub fn from_buffer_slice<F>(context: &F, buffer: BufferView<[T]>, offset: usize, size: usize,
+                                 ty: BufferTextureType)
+                                -> Result<BufferTexture<T>, (TextureCreationError, BufferView<[T]>)>
+                                where F: Facade
+    {
+        BufferTexture::from_buffer_impl(context, buffer, ty, Some((offset, size)))
+    }
+
+    /// Returns the maximum number of texels a buffer texture is allowed to expose, as reported
+    /// by the driver through `GL_MAX_TEXTURE_BUFFER_SIZE`.
+    ///
+    /// The value is queried once per thread and cached (see `MAX_TEXTURE_BUFFER_SIZE`), so sizing your
+    /// data against this before calling `new`/`from_buffer` is cheap and lets you avoid hitting
+    /// `TextureCreationError::TooLarge` altogether.
+    pub fn max_size<F>(facade: &F) -> usize where F: Facade {
+        let context = facade.get_context();
+        let ctxt = context.make_current();
+
+        MAX_TEXTURE_BUFFER_SIZE.with(|cache| {
+            if let Some(value) = cache.get() {
+                return value;
+            }
+
+            let value = unsafe {
+                let mut value = mem::uninitialized();
+                ctxt.gl.GetIntegerv(gl::MAX_TEXTURE_BUFFER_SIZE, &mut value);
+                value
+            };
+
+            cache.set(Some(value));
+            value
+        }) as usize
+    }
+
+    fn from_buffer_impl<F>(context: &F, buffer: BufferView<[T]>, ty: BufferTextureType,
+                            range: Option<(usize, usize)>)
+                           -> Result<BufferTexture<T>, (TextureCreationError, BufferView<[T]>)>
+                           where F: Facade
     {
         let context = context.get_context();
         let mut ctxt = context.make_current();
 
         // before starting, we determine the internal format and check that buffer textures are
         // supported
-        let internal_format = if ctxt.version >= &Version(Api::Gl, 3, 0) ||
-                                 ctxt.extensions.gl_oes_texture_buffer ||
-                                 ctxt.extensions.gl_ext_texture_buffer
-        {
-            match (T::get_type(), ty) {
-                (TextureBufferContentType::U8, BufferTextureType::Float) => gl::R8,
-                (TextureBufferContentType::U8, BufferTextureType::Unsigned) => gl::R8UI,
-                (TextureBufferContentType::I8, BufferTextureType::Integral) => gl::R8I,
-                (TextureBufferContentType::U16, BufferTextureType::Float) => gl::R16,
-                (TextureBufferContentType::U16, BufferTextureType::Unsigned) => gl::R16UI,
-                (TextureBufferContentType::I16, BufferTextureType::Integral) => gl::R16I,
-                (TextureBufferContentType::U32, BufferTextureType::Unsigned) => gl::R32UI,
-                (TextureBufferContentType::I32, BufferTextureType::Integral) => gl::R32I,
-                (TextureBufferContentType::U8U8, BufferTextureType::Float) => gl::RG8,
-                (TextureBufferContentType::U8U8, BufferTextureType::Unsigned) => gl::RG8UI,
-                (TextureBufferContentType::I8I8, BufferTextureType::Integral) => gl::RG8I,
-                (TextureBufferContentType::U16U16, BufferTextureType::Float) => gl::RG16,
-                (TextureBufferContentType::U16U16, BufferTextureType::Unsigned) => gl::RG16UI,
-                (TextureBufferContentType::I16I16, BufferTextureType::Integral) => gl::RG16I,
-                (TextureBufferContentType::U32U32, BufferTextureType::Unsigned) => gl::RG32UI,
-                (TextureBufferContentType::I32I32, BufferTextureType::Integral) => gl::RG32I,
-                (TextureBufferContentType::U8U8U8U8, BufferTextureType::Float) => gl::RGBA8,
-                (TextureBufferContentType::U8U8U8U8, BufferTextureType::Unsigned) => gl::RGBA8UI,
-                (TextureBufferContentType::I8I8I8I8, BufferTextureType::Integral) => gl::RGBA8I,
-                (TextureBufferContentType::U16U16U16U16, BufferTextureType::Float) => gl::RGBA16,
-                (TextureBufferContentType::U16U16U16U16, BufferTextureType::Unsigned) => 
-                                                                                      gl::RGBA16UI,
-                (TextureBufferContentType::I16I16I16I16, BufferTextureType::Integral) => 
-                                                                                       gl::RGBA16I,
-                (TextureBufferContentType::U32U32U32U32, BufferTextureType::Unsigned) => 
-                                                                                      gl::RGBA32UI,
-                (TextureBufferContentType::I32I32I32I32, BufferTextureType::Integral) => 
-                                                                                       gl::RGBA32I,
-                (TextureBufferContentType::F16, BufferTextureType::Float) => gl::R16F,
-                (TextureBufferContentType::F32, BufferTextureType::Float) => gl::R32F,
-                (TextureBufferContentType::F16F16, BufferTextureType::Float) => gl::RG16F,
-                (TextureBufferContentType::F32F32, BufferTextureType::Float) => gl::RG32F,
-                (TextureBufferContentType::F16F16F16F16, BufferTextureType::Float) => gl::RGBA16F,
-                (TextureBufferContentType::F32F32F32F32, BufferTextureType::Float) => gl::RGBA32F,
-
-                (TextureBufferContentType::U32U32U32, BufferTextureType::Unsigned)
-                                            if ctxt.version >= &Version(Api::Gl, 4, 0) ||
-                                               ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32UI,
-                (TextureBufferContentType::I32I32I32, BufferTextureType::Integral)
-                                            if ctxt.version >= &Version(Api::Gl, 4, 0) ||
-                                               ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32I,
-                (TextureBufferContentType::F32F32F32, BufferTextureType::Float)
-                                            if ctxt.version >= &Version(Api::Gl, 4, 0) ||
-                                               ctxt.extensions.gl_arb_texture_buffer_object_rgb32
-                                                                                    => gl::RGB32F,
-
-                _ => return Err((TextureCreationError::FormatNotSupported, buffer))
-            }
+        let format = match internal_format(T::get_type(), ty) {
+            Some(format) => format,
+            None => return Err((TextureCreationError::FormatNotSupported, buffer)),
+        };
 
-        } else if ctxt.extensions.gl_arb_texture_buffer_object ||
-                  ctxt.extensions.gl_ext_texture_buffer_object
+        // sanity check: the format we just picked should always sample back as the type the
+        // caller asked for
+        debug_assert_eq!(client_format(&format), ty);
+
+        let modern = ctxt.version >= &Version(Api::Gl, 3, 0) ||
+                     ctxt.extensions.gl_oes_texture_buffer ||
+                     ctxt.extensions.gl_ext_texture_buffer;
+        let legacy = ctxt.extensions.gl_arb_texture_buffer_object ||
+                     ctxt.extensions.gl_ext_texture_buffer_object;
+
+        if !modern && !legacy {
+            return Err((TextureCreationError::NotSupported, buffer));
+        }
+
+        // the legacy (pre-GL3) path only ever exposed 4-component formats
+        if !modern && format.components != 4 {
+            return Err((TextureCreationError::FormatNotSupported, buffer));
+        }
+
+        // the 3-component integer/float formats need GL 4.0 or the dedicated extension on top
+        // of whatever exposed buffer textures in the first place
+        if format.requires_rgb32_ext && !(ctxt.version >= &Version(Api::Gl, 4, 0) ||
+                                           ctxt.extensions.gl_arb_texture_buffer_object_rgb32)
         {
-            match (T::get_type(), ty) {
-                (TextureBufferContentType::U8U8U8U8, BufferTextureType::Float) => gl::RGBA8,
-                (TextureBufferContentType::U16U16U16U16, BufferTextureType::Float) => gl::RGBA16,
-                (TextureBufferContentType::F16F16F16F16, BufferTextureType::Float) => gl::RGBA16F,
-                (TextureBufferContentType::F32F32F32F32, BufferTextureType::Float) => gl::RGBA32F,
-                (TextureBufferContentType::I8I8I8I8, BufferTextureType::Integral) => gl::RGBA8I,
-                (TextureBufferContentType::I16I16I16I16, BufferTextureType::Integral) =>
-                                                                                      gl::RGBA16I,
-                (TextureBufferContentType::I32I32I32I32, BufferTextureType::Integral) =>
-                                                                                      gl::RGBA32I,
-                (TextureBufferContentType::U8U8U8U8, BufferTextureType::Unsigned) => gl::RGBA8UI,
-                (TextureBufferContentType::U16U16U16U16, BufferTextureType::Unsigned) =>
-                                                                                      gl::RGBA16UI,
-                (TextureBufferContentType::U32U32U32U32, BufferTextureType::Unsigned) =>
-                                                                                      gl::RGBA32UI,
-
-                // TODO: intensity?
-
-                _ => return Err((TextureCreationError::FormatNotSupported, buffer))
+            return Err((TextureCreationError::FormatNotSupported, buffer));
+        }
+
+        let internal_format = format.gl_enum;
+
+        // checking that the number of texels we're about to expose fits within the device's
+        // GL_MAX_TEXTURE_BUFFER_SIZE, instead of letting the driver silently truncate or error
+        let max_texels = MAX_TEXTURE_BUFFER_SIZE.with(|cache| {
+            if let Some(value) = cache.get() {
+                return value;
             }
 
-        } else {
-            return Err((TextureCreationError::NotSupported, buffer));
-        };
+            let value = unsafe {
+                let mut value = mem::uninitialized();
+                ctxt.gl.GetIntegerv(gl::MAX_TEXTURE_BUFFER_SIZE, &mut value);
+                value
+            };
+
+            cache.set(Some(value));
+            value
+        }) as usize;
+        let num_texels = range.map(|(_, size)| size).unwrap_or_else(|| buffer.len());
+        if num_texels > max_texels {
+            return Err((TextureCreationError::TooLarge, buffer));
+        }
 
-        // FIXME: check `TooLarge` error
+        // turning the element offset/count of a sub-range into a byte range, checking that the
+        // offset respects the driver's alignment requirements and that ranged binding is
+        // actually available
+        let range_bytes = match range {
+            Some((offset, size)) => {
+                let in_bounds = offset.checked_add(size).map_or(false, |end| end <= buffer.len());
+                if !in_bounds {
+                    return Err((TextureCreationError::OutOfRange, buffer));
+                }
+
+                let range_supported = ctxt.version >= &Version(Api::Gl, 4, 3) ||
+                                       ctxt.extensions.gl_arb_texture_buffer_range ||
+                                       ctxt.extensions.gl_ext_texture_buffer ||
+                                       ctxt.extensions.gl_oes_texture_buffer;
+
+                if !range_supported {
+                    return Err((TextureCreationError::NotSupported, buffer));
+                }
+
+                let offset_bytes = buffer.get_offset_bytes() + offset * mem::size_of::<T>();
+                let size_bytes = size * mem::size_of::<T>();
+
+                let alignment = unsafe {
+                    let mut value = mem::uninitialized();
+                    ctxt.gl.GetIntegerv(gl::TEXTURE_BUFFER_OFFSET_ALIGNMENT, &mut value);
+                    value as usize
+                };
+
+                if alignment != 0 && offset_bytes % alignment != 0 {
+                    return Err((TextureCreationError::BadAlignment, buffer));
+                }
+
+                Some((offset_bytes, size_bytes))
+            },
+            None => {
+                debug_assert_eq!(buffer.get_offset_bytes(), 0);
+                None
+            },
+        };
 
-        // TODO: use DSA if available
+        let dsa = ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access;
 
         // reserving the ID
         let id = unsafe {
             let mut id = mem::uninitialized();
-            ctxt.gl.GenTextures(1, &mut id);
+            if dsa {
+                ctxt.gl.CreateTextures(gl::TEXTURE_BUFFER, 1, &mut id);
+            } else {
+                ctxt.gl.GenTextures(1, &mut id);
+            }
             id
         };
 
-        // binding the texture
-        unsafe {
-            ctxt.gl.BindTexture(gl::TEXTURE_BUFFER, id);
-            let act = ctxt.state.active_texture as usize;
-            ctxt.state.texture_units[act].texture = id;
-        }
-
-        // binding the buffer
-        debug_assert_eq!(buffer.get_offset_bytes(), 0);
-        if ctxt.version >= &Version(Api::Gl, 3, 0) {
+        if dsa {
+            // DSA lets us attach the buffer's storage directly on the texture name, without
+            // ever going through a texture unit. The currently bound texture (if any) is left
+            // completely untouched.
             unsafe {
-                ctxt.gl.TexBuffer(gl::TEXTURE_BUFFER, internal_format, buffer.get_buffer_id());
+                if let Some((offset_bytes, size_bytes)) = range_bytes {
+                    ctxt.gl.TextureBufferRange(id, internal_format, buffer.get_buffer_id(),
+                                               offset_bytes as gl::types::GLintptr,
+                                               size_bytes as gl::types::GLsizeiptr);
+                } else {
+                    ctxt.gl.TextureBuffer(id, internal_format, buffer.get_buffer_id());
+                }
             }
-        } else if ctxt.extensions.gl_arb_texture_buffer_object {
-            unsafe {
-                ctxt.gl.TexBufferARB(gl::TEXTURE_BUFFER, internal_format, buffer.get_buffer_id());
-            }
-        } else if ctxt.extensions.gl_ext_texture_buffer_object ||
-                  ctxt.extensions.gl_ext_texture_buffer
-        {
+        } else {
+            // binding the texture
             unsafe {
-                ctxt.gl.TexBufferEXT(gl::TEXTURE_BUFFER, internal_format, buffer.get_buffer_id());
+                ctxt.gl.BindTexture(gl::TEXTURE_BUFFER, id);
+                let act = ctxt.state.active_texture as usize;
+                ctxt.state.texture_units[act].texture = id;
             }
-        } else if ctxt.extensions.gl_oes_texture_buffer {
-            unsafe {
-                ctxt.gl.TexBufferOES(gl::TEXTURE_BUFFER, internal_format, buffer.get_buffer_id());
+
+            // binding the buffer
+            if let Some((offset_bytes, size_bytes)) = range_bytes {
+                let offset_bytes = offset_bytes as gl::types::GLintptr;
+                let size_bytes = size_bytes as gl::types::GLsizeiptr;
+
+                if ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.extensions.gl_arb_texture_buffer_range {
+                    unsafe {
+                        ctxt.gl.TexBufferRange(gl::TEXTURE_BUFFER, internal_format,
+                                               buffer.get_buffer_id(), offset_bytes, size_bytes);
+                    }
+                } else if ctxt.extensions.gl_ext_texture_buffer {
+                    unsafe {
+                        ctxt.gl.TexBufferRangeEXT(gl::TEXTURE_BUFFER, internal_format,
+                                                  buffer.get_buffer_id(), offset_bytes, size_bytes);
+                    }
+                } else if ctxt.extensions.gl_oes_texture_buffer {
+                    unsafe {
+                        ctxt.gl.TexBufferRangeOES(gl::TEXTURE_BUFFER, internal_format,
+                                                  buffer.get_buffer_id(), offset_bytes, size_bytes);
+                    }
+                } else {
+                    // handled above ; note that this will leak the texture
+                    unreachable!();
+                }
+            } else if ctxt.version >= &Version(Api::Gl, 3, 0) {
+                unsafe {
+                    ctxt.gl.TexBuffer(gl::TEXTURE_BUFFER, internal_format, buffer.get_buffer_id());
+                }
+            } else if ctxt.extensions.gl_arb_texture_buffer_object {
+                unsafe {
+                    ctxt.gl.TexBufferARB(gl::TEXTURE_BUFFER, internal_format, buffer.get_buffer_id());
+                }
+            } else if ctxt.extensions.gl_ext_texture_buffer_object ||
+                      ctxt.extensions.gl_ext_texture_buffer
+            {
+                unsafe {
+                    ctxt.gl.TexBufferEXT(gl::TEXTURE_BUFFER, internal_format, buffer.get_buffer_id());
+                }
+            } else if ctxt.extensions.gl_oes_texture_buffer {
+                unsafe {
+                    ctxt.gl.TexBufferOES(gl::TEXTURE_BUFFER, internal_format, buffer.get_buffer_id());
+                }
+            } else {
+                // handled above ; note that this will leak the texture
+                unreachable!();
             }
-        } else {
-            // handled above ; note that this will leak the texture
-            unreachable!();
         }
 
         Ok(BufferTexture {
             buffer: buffer,
             ty: ty,
             texture: id,
+            format: format,
         })
     }
+
+    /// Returns the number of bytes a single texel of this buffer texture takes up.
+    ///
+    /// Derived from the `InternalFormat` picked at creation time; useful for sizing a
+    /// `glGetTextureImage`-style readback.
+    pub fn bytes_per_texel(&self) -> usize {
+        self.format.bytes_per_texel()
+    }
+
+    /// Makes this buffer texture GPU-resident, returning a handle that can be sampled from a
+    /// shader without ever binding it to a texture unit.
+    ///
+    /// This requires `GL_ARB_bindless_texture`. If the extension isn't supported the texture is
+    /// handed back unchanged alongside `TextureCreationError::NotSupported`.
+    ///
+    /// The returned handle stays valid for as long as the `ResidentBufferTexture` lives; it must
+    /// not be re-fetched, as `glGetTextureHandleARB` may only be called once per texture.
+    pub fn make_resident(self)
+                         -> Result<ResidentBufferTexture<T>, (TextureCreationError, BufferTexture<T>)>
+    {
+        let mut ctxt = self.buffer.get_context().make_current();
+
+        if !ctxt.extensions.gl_arb_bindless_texture {
+            return Err((TextureCreationError::NotSupported, self));
+        }
+
+        let handle = unsafe { ctxt.gl.GetTextureHandleARB(self.texture) };
+        unsafe { ctxt.gl.MakeTextureHandleResidentARB(handle); }
+
+        Ok(ResidentBufferTexture { texture: self, handle: handle })
+    }
 }
 
 impl<T> Deref for BufferTexture<T> where [T]: BufferContent {
@@ -339,6 +600,44 @@ impl<T> Drop for BufferTexture<T> where [T]: BufferContent {
     }
 }
 
+/// A `BufferTexture` that has been made GPU-resident through `GL_ARB_bindless_texture`.
+///
+/// Built by calling `BufferTexture::make_resident`. As long as this value is alive, the texture
+/// can be sampled from any shader without occupying one of the draw call's limited texture
+/// units, which is what lets a single SSBO/UBO of handles index arbitrarily many buffer
+/// textures. Use `handle()` to read the raw `u64` to store alongside others in such a buffer.
+///
+/// Feeding a single handle into an individual `sampler`/`usamplerBuffer`/`isamplerBuffer`
+/// uniform instead requires a `uniforms::UniformValue` variant for `GL_ARB_bindless_texture`
+/// handles; that lives outside this module and isn't added here.
+pub struct ResidentBufferTexture<T> where [T]: BufferContent {
+    texture: BufferTexture<T>,
+    handle: gl::types::GLuint64,
+}
+
+impl<T> ResidentBufferTexture<T> where [T]: BufferContent {
+    /// Returns the bindless handle, for example to store alongside others in an SSBO/UBO that a
+    /// shader indexes into.
+    pub fn handle(&self) -> u64 {
+        self.handle
+    }
+}
+
+impl<T> Deref for ResidentBufferTexture<T> where [T]: BufferContent {
+    type Target = BufferTexture<T>;
+
+    fn deref(&self) -> &BufferTexture<T> {
+        &self.texture
+    }
+}
+
+impl<T> Drop for ResidentBufferTexture<T> where [T]: BufferContent {
+    fn drop(&mut self) {
+        let mut ctxt = self.texture.buffer.get_context().make_current();
+        unsafe { ctxt.gl.MakeTextureHandleNonResidentARB(self.handle); }
+    }
+}
+
 ///
 ///
 /// Note that some three-component types are missing. This is not a mistake. OpenGL doesn't